@@ -0,0 +1,88 @@
+//! OpenSea-style trait/attribute metadata for `--attribute` and
+//! `--metadata-json`, serialized into a token's `key_val_data`.
+
+use std::{collections::HashMap, fs::File, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::MetadataVal;
+
+/// A single OpenSea-style trait entry.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Attribute {
+    pub trait_type: String,
+    pub value: AttributeValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+}
+
+/// An attribute's value, numeric or text.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Number(f64),
+    Text(String),
+}
+
+/// The `--metadata-json` document: display name, description, and a list of
+/// attributes, mirroring the OpenSea-style metadata-extension standard.
+#[derive(Deserialize, Default)]
+pub struct MetadataJson {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<Attribute>,
+}
+
+/// Reads a `--metadata-json` file.
+pub fn read_json(path: &Path) -> Result<MetadataJson> {
+    let file = File::open(path).with_context(|| format!("opening {path:?}"))?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Parses a single `--attribute key=value` flag, inferring a numeric or text
+/// `MetadataVal` for `value`.
+pub fn parse(raw: &str) -> Result<(String, MetadataVal)> {
+    let (key, value) = raw
+        .split_once('=')
+        .with_context(|| format!("--attribute {raw:?} must be in the form key=value"))?;
+    let val = match value.parse::<u128>() {
+        Ok(n) => MetadataVal::NatContent(n),
+        Err(_) => MetadataVal::TextContent(value.to_string()),
+    };
+    Ok((key.to_string(), val))
+}
+
+/// Inserts `name`, `description`, and a serialized `attributes` list from
+/// `json`, plus any `--attribute key=value` overrides, into `key_val_data`.
+pub fn apply(
+    key_val_data: &mut HashMap<String, MetadataVal>,
+    json: Option<MetadataJson>,
+    attributes: &[String],
+) -> Result<()> {
+    let mut traits = Vec::new();
+    if let Some(json) = json {
+        if let Some(name) = json.name {
+            key_val_data.insert("name".to_string(), MetadataVal::TextContent(name));
+        }
+        if let Some(description) = json.description {
+            key_val_data.insert(
+                "description".to_string(),
+                MetadataVal::TextContent(description),
+            );
+        }
+        traits = json.attributes;
+    }
+    for raw in attributes {
+        let (key, val) = parse(raw)?;
+        key_val_data.insert(key, val);
+    }
+    if !traits.is_empty() {
+        key_val_data.insert(
+            "attributes".to_string(),
+            MetadataVal::TextContent(serde_json::to_string(&traits)?),
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,247 @@
+//! Packs a file into a CAR (content-addressed archive) using UnixFS chunking
+//! and uploads it to an IPFS pinning service, so the CID stored on-chain is
+//! guaranteed to match the bytes actually pinned rather than a hand-typed one.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use cid::Cid;
+use multihash::Multihash;
+use sha2::{Digest, Sha256};
+
+const CHUNK_SIZE: usize = 256 * 1024;
+const SHA2_256: u64 = 0x12;
+const RAW_CODEC: u64 = 0x55;
+const DAG_PB_CODEC: u64 = 0x70;
+
+/// Identifies this tool to pinning services, following the metaplex-auth
+/// convention of tagging upload requests with an agent string.
+const MINTING_AGENT: &str = concat!("experimental-minting-tool/", env!("CARGO_PKG_VERSION"));
+
+/// Reads `file`, packs it into a CARv1 archive, POSTs it to `endpoint` with
+/// `token` as a bearer credential, and returns the CAR's root CID.
+pub async fn upload(file: &Path, endpoint: &str, token: &str) -> Result<Cid> {
+    let data = std::fs::read(file).with_context(|| format!("reading {file:?}"))?;
+    let (root, car) = build_car(&data)?;
+    let file_name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "upload.car".to_string());
+    let part = reqwest::multipart::Part::bytes(car)
+        .file_name(file_name)
+        .mime_str("application/vnd.ipld.car")?;
+    let form = reqwest::multipart::Form::new().part("file", part);
+    reqwest::Client::new()
+        .post(endpoint)
+        .bearer_auth(token)
+        .header("mintingAgent", MINTING_AGENT)
+        .multipart(form)
+        .send()
+        .await
+        .context("uploading CAR to pinning endpoint")?
+        .error_for_status()
+        .context("pinning endpoint rejected the upload")?;
+    Ok(root)
+}
+
+/// Chunks `data` into UnixFS leaves, builds the CARv1 bytes for them, and
+/// returns the archive's root CID alongside the archive itself.
+fn build_car(data: &[u8]) -> Result<(Cid, Vec<u8>)> {
+    let chunks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[]]
+    } else {
+        data.chunks(CHUNK_SIZE).collect()
+    };
+    let (root, blocks) = if chunks.len() == 1 {
+        let block = chunks[0].to_vec();
+        let cid = raw_leaf_cid(&block)?;
+        (cid, vec![(cid, block)])
+    } else {
+        let mut blocks = Vec::with_capacity(chunks.len() + 1);
+        let mut links = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            let cid = raw_leaf_cid(chunk)?;
+            links.push((cid, chunk.len() as u64));
+            blocks.push((cid, chunk.to_vec()));
+        }
+        let root_data = unixfs_file_root(data.len() as u64, &links);
+        let root_cid = dag_pb_cid(&root_data)?;
+        blocks.push((root_cid, root_data));
+        (root_cid, blocks)
+    };
+    let car = write_car(root, &blocks)?;
+    Ok((root, car))
+}
+
+fn raw_leaf_cid(chunk: &[u8]) -> Result<Cid> {
+    let digest = Sha256::digest(chunk);
+    let mh = Multihash::wrap(SHA2_256, &digest)?;
+    Ok(Cid::new_v1(RAW_CODEC, mh))
+}
+
+fn dag_pb_cid(bytes: &[u8]) -> Result<Cid> {
+    let digest = Sha256::digest(bytes);
+    let mh = Multihash::wrap(SHA2_256, &digest)?;
+    Ok(Cid::new_v1(DAG_PB_CODEC, mh))
+}
+
+/// Builds the dag-pb bytes for a UnixFS `File` node linking to `links`
+/// (child CID, byte length), the way an IPFS importer would for a
+/// multi-chunk file.
+fn unixfs_file_root(filesize: u64, links: &[(Cid, u64)]) -> Vec<u8> {
+    let blocksizes: Vec<u64> = links.iter().map(|(_, size)| *size).collect();
+    let data = unixfs_file_data(filesize, &blocksizes);
+    let mut buf = Vec::new();
+    for (cid, size) in links {
+        let link = pb_link(*cid, "", *size);
+        write_bytes_field(&mut buf, 2, &link);
+    }
+    write_bytes_field(&mut buf, 1, &data);
+    buf
+}
+
+/// Encodes a UnixFS `Data` protobuf message with `Type = File`.
+fn unixfs_file_data(filesize: u64, blocksizes: &[u64]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_varint_field(&mut buf, 1, 2); // Type = File
+    write_varint_field(&mut buf, 3, filesize);
+    for size in blocksizes {
+        write_varint_field(&mut buf, 4, *size);
+    }
+    buf
+}
+
+/// Encodes a dag-pb `PBLink` protobuf message.
+fn pb_link(cid: Cid, name: &str, tsize: u64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_bytes_field(&mut buf, 1, &cid.to_bytes());
+    write_bytes_field(&mut buf, 2, name.as_bytes());
+    write_varint_field(&mut buf, 3, tsize);
+    buf
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn write_tag(buf: &mut Vec<u8>, field: u32, wire_type: u32) {
+    write_varint(buf, ((field as u64) << 3) | wire_type as u64);
+}
+
+fn write_bytes_field(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_tag(buf, field, 2);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    write_tag(buf, field, 0);
+    write_varint(buf, value);
+}
+
+/// Encodes the fixed-shape dag-cbor CAR header `{"version": 1, "roots": [root]}`.
+fn car_header(root: Cid) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.push(0xa2); // map(2)
+    buf.push(0x67); // text(7)
+    buf.extend_from_slice(b"version");
+    buf.push(0x01);
+    buf.push(0x65); // text(5)
+    buf.extend_from_slice(b"roots");
+    buf.push(0x81); // array(1)
+    buf.push(0xd8); // tag, 1-byte value follows
+    buf.push(42); // tag 42: CID-as-link
+    let mut identity_wrapped = Vec::with_capacity(root.to_bytes().len() + 1);
+    identity_wrapped.push(0x00); // identity multibase prefix required by dag-cbor CIDs
+    identity_wrapped.extend_from_slice(&root.to_bytes());
+    write_cbor_bytes(&mut buf, &identity_wrapped);
+    buf
+}
+
+fn write_cbor_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    let len = bytes.len();
+    if len < 24 {
+        buf.push(0x40 | len as u8);
+    } else if len < 256 {
+        buf.push(0x58);
+        buf.push(len as u8);
+    } else {
+        buf.push(0x59);
+        buf.extend_from_slice(&(len as u16).to_be_bytes());
+    }
+    buf.extend_from_slice(bytes);
+}
+
+/// Writes a CARv1 archive: a varint-prefixed dag-cbor header followed by
+/// varint-prefixed `(cid, data)` blocks.
+fn write_car(root: Cid, blocks: &[(Cid, Vec<u8>)]) -> Result<Vec<u8>> {
+    let header = car_header(root);
+    let mut out = Vec::new();
+    write_varint(&mut out, header.len() as u64);
+    out.extend_from_slice(&header);
+    for (cid, data) in blocks {
+        let cid_bytes = cid.to_bytes();
+        write_varint(&mut out, (cid_bytes.len() + data.len()) as u64);
+        out.extend_from_slice(&cid_bytes);
+        out.extend_from_slice(data);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_varint_matches_leb128() {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 1);
+        assert_eq!(buf, vec![0x01]);
+        buf.clear();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn raw_leaf_cid_hashes_with_sha256_and_the_raw_codec() {
+        let chunk = b"hello ipfs";
+        let cid = raw_leaf_cid(chunk).unwrap();
+        assert_eq!(cid.codec(), RAW_CODEC);
+        assert_eq!(cid.hash().code(), SHA2_256);
+        assert_eq!(cid.hash().digest(), Sha256::digest(chunk).as_slice());
+    }
+
+    #[test]
+    fn single_chunk_file_is_its_own_raw_leaf() {
+        // a file under CHUNK_SIZE has no UnixFS wrapper node; the CAR's root
+        // is the bare raw leaf, byte-for-byte the same as raw_leaf_cid's own
+        // block, appended verbatim at the end of the archive.
+        let data = b"tiny file contents";
+        let (root, car) = build_car(data).unwrap();
+        assert_eq!(root, raw_leaf_cid(data).unwrap());
+        assert!(car.ends_with(data));
+    }
+
+    #[test]
+    fn multi_chunk_file_wraps_leaves_in_a_unixfs_root() {
+        let data = vec![7u8; CHUNK_SIZE + 10];
+        let (root, car) = build_car(&data).unwrap();
+        // the root is now a dag-pb node distinct from either leaf's raw CID
+        assert_eq!(root.codec(), DAG_PB_CODEC);
+        let first_leaf = raw_leaf_cid(&data[..CHUNK_SIZE]).unwrap();
+        let second_leaf = raw_leaf_cid(&data[CHUNK_SIZE..]).unwrap();
+        assert_ne!(root, first_leaf);
+        assert_ne!(root, second_leaf);
+        // both leaves and the root node must be present as blocks in the archive
+        assert!(car.windows(first_leaf.to_bytes().len()).any(|w| w == first_leaf.to_bytes()));
+        assert!(car.windows(second_leaf.to_bytes().len()).any(|w| w == second_leaf.to_bytes()));
+    }
+}
@@ -16,15 +16,17 @@ use candid::Principal;
 use cid::Cid;
 use clap::Parser;
 use dialoguer::Confirm;
-use ic_agent::{
-    agent::http_transport::ReqwestHttpReplicaV2Transport, identity::BasicIdentity, Agent,
-    AgentError,
-};
+use ic_agent::{agent::http_transport::ReqwestHttpReplicaV2Transport, Agent, AgentError};
 use sha2::{Digest, Sha256};
 use types::*;
 use uriparse::URI;
 
+mod attributes;
+mod identity;
+mod ipfs;
+mod provenance;
 mod types;
+mod verify;
 
 #[tokio::main]
 async fn main() {
@@ -36,7 +38,9 @@ async fn main() {
 
 async fn rmain() -> Result<()> {
     let mint = Args::parse();
-    if mint.file.is_none()
+    let canister = mint.canister;
+    if mint.manifest.is_none()
+        && mint.file.is_none()
         && !mint.yes
         && !Confirm::new()
             .with_prompt("Are you sure you don't want to specify a file? No content will be uploaded, only metadata!")
@@ -45,13 +49,75 @@ async fn rmain() -> Result<()> {
         println!("Aborted upload");
         return Ok(())
     }
-    let canister = mint.canister;
-    let owner = mint.owner;
     let agent = get_agent(
         &mint.network,
+        mint.identity.as_deref(),
         mint.fetch_root_key || mint.network == "local",
     )
     .await?;
+    let interfaces = supported_interfaces(&agent, canister).await?;
+    if !interfaces.contains(&InterfaceId::Mint) {
+        bail!("canister {canister} does not support minting");
+    }
+    if let Some(manifest) = mint.manifest {
+        let defaults = BatchDefaults {
+            wallet: mint.wallet,
+            ipfs_gateway: mint.ipfs_gateway,
+            verify: mint.verify,
+            attributes: mint.attributes,
+            metadata_json: mint.metadata_json,
+            derived_from: mint.derived_from,
+            derive_method: mint.derive_method,
+            derive_params: mint.derive_params,
+            upload_ipfs: mint.upload_ipfs,
+            ipfs_pin_endpoint: mint.ipfs_pin_endpoint,
+            ipfs_pin_token: mint.ipfs_pin_token,
+        };
+        return mint_batch(&agent, canister, &manifest, defaults, mint.yes).await;
+    }
+    let owner = mint.owner.expect("required unless --manifest is given");
+    let ipfs_location = if mint.upload_ipfs {
+        let file = mint
+            .file
+            .as_deref()
+            .expect("required by clap when --upload-ipfs is set");
+        let token = mint
+            .ipfs_pin_token
+            .as_deref()
+            .context("--ipfs-pin-token is required with --upload-ipfs")?;
+        let cid = ipfs::upload(file, &mint.ipfs_pin_endpoint, token).await?;
+        println!("Uploaded {} to IPFS as {cid}", file.display());
+        Some(cid.to_string())
+    } else {
+        mint.ipfs_location
+    };
+    let source = Source {
+        ipfs_location,
+        asset_canister: mint.asset_canister,
+        uri: mint.uri,
+        file: mint.file,
+        sha2: mint.sha2,
+        sha2_auto: mint.sha2_auto,
+        mime_type: mint.mime_type,
+        attributes: mint.attributes,
+        metadata_json: mint.metadata_json,
+        verify: mint.verify,
+        ipfs_gateway: mint.ipfs_gateway,
+        derived_from: mint.derived_from,
+        derive_method: mint.derive_method,
+        derive_params: mint.derive_params,
+    };
+    let receipt = mint_one(&agent, canister, owner, source, mint.wallet).await?;
+    println!(
+        "Successfully minted token {} to {owner} (transaction id {})",
+        receipt.token_id, receipt.id
+    );
+    Ok(())
+}
+
+/// Fetches the set of DIP-721 interfaces the canister advertises, translating
+/// the "not a DIP-721 canister at all" case into a friendlier error.
+async fn supported_interfaces(agent: &Agent, canister: Principal) -> Result<Vec<InterfaceId>> {
     let res = agent
         .query(&canister, "supportedInterfacesDip721")
         .with_arg(Encode!()?)
@@ -64,64 +130,390 @@ async fn rmain() -> Result<()> {
     } else {
         res?
     };
-    let interfaces = Decode!(&res, Vec<InterfaceId>)?;
-    if !interfaces.contains(&InterfaceId::Mint) {
-        bail!("canister {canister} does not support minting");
-    }
+    Ok(Decode!(&res, Vec<InterfaceId>)?)
+}
+
+/// The content source for a single token, mirroring the mutually exclusive
+/// `--ipfs-location`/`--asset-canister`/`--uri`/`--file` flags of [`Args`].
+struct Source {
+    ipfs_location: Option<String>,
+    asset_canister: Option<Principal>,
+    uri: Option<String>,
+    file: Option<PathBuf>,
+    sha2: Option<String>,
+    sha2_auto: bool,
+    mime_type: Option<String>,
+    attributes: Vec<String>,
+    metadata_json: Option<PathBuf>,
+    verify: bool,
+    ipfs_gateway: String,
+    derived_from: Vec<u64>,
+    derive_method: Option<String>,
+    derive_params: Option<String>,
+}
+
+/// Builds the `key_val_data` map and raw file bytes for a single token from
+/// its [`Source`], the same way `rmain` did for the one-shot case. If
+/// `source.verify` is set, fetches any remote source and aborts unless its
+/// SHA-256 matches the advertised `contentHash`.
+async fn build_metadata(agent: &Agent, source: &Source) -> Result<(HashMap<String, MetadataVal>, Vec<u8>)> {
     let mut metadata = HashMap::new();
     use MetadataVal::*;
-    if let Some(ipfs_location) = mint.ipfs_location {
-        metadata.insert("locationType", Nat8Content(1));
+    if let Some(ipfs_location) = &source.ipfs_location {
+        metadata.insert("locationType".into(), Nat8Content(1));
         let cid: Cid = ipfs_location.parse()?;
-        metadata.insert("location", BlobContent(cid.to_bytes()));
-    } else if let Some(asset_canister) = mint.asset_canister {
-        metadata.insert("locationType", Nat8Content(2));
-        metadata.insert("location", TextContent(format!("{asset_canister}")));
-    } else if let Some(uri) = mint.uri {
-        URI::try_from(&*uri)?;
-        metadata.insert("locationType", Nat8Content(3));
-        metadata.insert("location", TextContent(uri));
+        metadata.insert("location".into(), BlobContent(cid.to_bytes()));
+    } else if let Some(asset_canister) = source.asset_canister {
+        metadata.insert("locationType".into(), Nat8Content(2));
+        metadata.insert("location".into(), TextContent(format!("{asset_canister}")));
+    } else if let Some(uri) = &source.uri {
+        URI::try_from(&**uri)?;
+        if source.sha2.is_none() && !source.sha2_auto {
+            bail!("a uri source requires a contentHash: supply --sha2 or --sha2-auto");
+        }
+        metadata.insert("locationType".into(), Nat8Content(3));
+        metadata.insert("location".into(), TextContent(uri.clone()));
     } else {
-        metadata.insert("locationType", Nat8Content(4));
+        metadata.insert("locationType".into(), Nat8Content(4));
     }
-    if let Some(sha2) = mint.sha2 {
+    if let Some(sha2) = &source.sha2 {
         let hex = hex::decode(sha2)?;
-        metadata.insert("contentHash", BlobContent(hex));
+        metadata.insert("contentHash".into(), BlobContent(hex));
     }
-    let (data, content_type) = if let Some(file) = mint.file {
-        let data = fs::read(&file)?;
-        if mint.sha2_auto {
+    let (data, content_type) = if let Some(file) = &source.file {
+        let data = fs::read(file)?;
+        if source.sha2_auto {
             metadata.insert(
-                "contentHash",
+                "contentHash".into(),
                 BlobContent(Vec::from_iter(Sha256::digest(&data))),
             );
         }
-        let content_type = mint
+        let content_type = source
             .mime_type
-            .or_else(|| mime_guess::from_path(&file).first().map(|m| format!("{m}")));
+            .clone()
+            .or_else(|| mime_guess::from_path(file).first().map(|m| format!("{m}")));
         (data, content_type)
     } else {
-        (vec![], mint.mime_type)
+        (vec![], source.mime_type.clone())
     };
     let content_type = content_type.unwrap_or_else(|| String::from("application/octet-stream"));
-    metadata.insert("contentType", TextContent(content_type));
+    metadata.insert("contentType".into(), TextContent(content_type));
+    let json = source
+        .metadata_json
+        .as_deref()
+        .map(attributes::read_json)
+        .transpose()?;
+    attributes::apply(&mut metadata, json, &source.attributes)?;
+    provenance::apply(
+        &mut metadata,
+        &source.derived_from,
+        source.derive_method.as_deref(),
+        source.derive_params.as_deref(),
+    );
+    if source.verify {
+        let Some(MetadataVal::BlobContent(expected)) = metadata.get("contentHash") else {
+            bail!("--verify requires a contentHash to check against; supply --sha2 or --sha2-auto");
+        };
+        verify::check(
+            agent,
+            &source.ipfs_gateway,
+            source.ipfs_location.as_deref(),
+            source.asset_canister,
+            source.uri.as_deref(),
+            expected,
+        )
+        .await?;
+    }
+    Ok((metadata, data))
+}
+
+/// Mints a single token and returns the canister's receipt, translating the
+/// "canister doesn't support minting" replica error into a friendlier one.
+/// When `wallet` is given, the call is forwarded through the wallet's
+/// `wallet_call128` rather than made directly, for collections where the
+/// wallet itself is the authorized custodian.
+async fn mint_one(
+    agent: &Agent,
+    canister: Principal,
+    owner: Principal,
+    source: Source,
+    wallet: Option<Principal>,
+) -> Result<MintReceipt> {
+    let (key_val_data, data) = build_metadata(agent, &source).await?;
     let metadata = MetadataPart {
         purpose: MetadataPurpose::Rendered,
         data: &data,
-        key_val_data: metadata,
+        key_val_data,
+    };
+    let mint_args = Encode!(&owner, &[metadata], &data)?;
+    let reply = if let Some(wallet) = wallet {
+        call_through_wallet(agent, wallet, canister, "mintDip721", mint_args).await?
+    } else {
+        let res = agent
+            .update(&canister, "mintDip721")
+            .with_arg(mint_args)
+            .call_and_wait()
+            .await;
+        if let Err(AgentError::ReplicaError { reject_code: 3, .. }) = &res {
+            res.context(format!("canister {canister} does not support minting"))?
+        } else {
+            res?
+        }
+    };
+    Ok(Decode!(&reply, Result<MintReceipt, MintError>)??)
+}
+
+/// Calls `method_name` on `canister` indirectly through `wallet`'s
+/// `wallet_call128`, so the effective caller is the wallet rather than this
+/// tool's own identity.
+async fn call_through_wallet(
+    agent: &Agent,
+    wallet: Principal,
+    canister: Principal,
+    method_name: &'static str,
+    args: Vec<u8>,
+) -> Result<Vec<u8>> {
+    let wallet_args = WalletCallArgs {
+        canister,
+        method_name,
+        args: &args,
+        cycles: 0,
     };
     let res = agent
-        .update(&mint.canister, "mintDip721")
-        .with_arg(Encode!(&owner, &[metadata], &data)?)
+        .update(&wallet, "wallet_call128")
+        .with_arg(Encode!(&wallet_args)?)
         .call_and_wait()
         .await;
     let res = if let Err(AgentError::ReplicaError { reject_code: 3, .. }) = &res {
-        res.context(format!("canister {canister} does not support minting"))?
+        res.context(format!("{wallet} does not appear to be a cycles wallet"))?
     } else {
         res?
     };
-    let MintReceipt { token_id, id } = Decode!(&res, Result<MintReceipt, MintError>)??;
-    println!("Successfully minted token {token_id} to {owner} (transaction id {id})");
+    match Decode!(&res, WalletCallResponse)? {
+        WalletCallResponse::Ok(result) => Ok(result.return_),
+        WalletCallResponse::Err(e) => bail!("wallet call to {canister}.{method_name} failed: {e}"),
+    }
+}
+
+/// One row of a minting manifest, describing a single token to mint as part
+/// of a `--manifest` collection run. `attributes` and `derived_from` accept
+/// either a native JSON array or, for CSV rows, a single `;`-separated cell
+/// (the `csv` crate's serde support only special-cases a trailing `Vec`
+/// field by slurping the rest of the row, which would misalign every column
+/// after it).
+#[derive(Deserialize)]
+struct ManifestRow {
+    owner: Principal,
+    file: Option<PathBuf>,
+    ipfs_location: Option<String>,
+    asset_canister: Option<Principal>,
+    uri: Option<String>,
+    sha2: Option<String>,
+    #[serde(default)]
+    sha2_auto: bool,
+    #[serde(default)]
+    upload_ipfs: bool,
+    mime_type: Option<String>,
+    #[serde(default, deserialize_with = "string_list")]
+    attributes: Vec<String>,
+    metadata_json: Option<PathBuf>,
+    #[serde(default)]
+    verify: bool,
+    #[serde(default, deserialize_with = "u64_list")]
+    derived_from: Vec<u64>,
+    derive_method: Option<String>,
+    derive_params: Option<String>,
+}
+
+/// Deserializes a JSON array of strings or a `;`-separated CSV cell.
+fn string_list<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    struct ListVisitor;
+    impl<'de> serde::de::Visitor<'de> for ListVisitor {
+        type Value = Vec<String>;
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a JSON array of strings, or a ';'-separated string")
+        }
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(if v.is_empty() {
+                vec![]
+            } else {
+                v.split(';').map(|s| s.trim().to_string()).collect()
+            })
+        }
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut out = Vec::new();
+            while let Some(item) = seq.next_element()? {
+                out.push(item);
+            }
+            Ok(out)
+        }
+    }
+    deserializer.deserialize_any(ListVisitor)
+}
+
+/// Deserializes a JSON array of numbers or a `;`-separated CSV cell.
+fn u64_list<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+    struct ListVisitor;
+    impl<'de> serde::de::Visitor<'de> for ListVisitor {
+        type Value = Vec<u64>;
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a JSON array of integers, or a ';'-separated string")
+        }
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if v.is_empty() {
+                return Ok(vec![]);
+            }
+            v.split(';')
+                .map(|s| s.trim().parse().map_err(E::custom))
+                .collect()
+        }
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut out = Vec::new();
+            while let Some(item) = seq.next_element()? {
+                out.push(item);
+            }
+            Ok(out)
+        }
+    }
+    deserializer.deserialize_any(ListVisitor)
+}
+
+/// Reads a manifest file (`.csv` or `.json`, a JSON array of rows) describing
+/// a whole collection, in the style of the ORIGYN minting-starter workflow
+/// that mints an entire collection from the local file system.
+fn read_manifest(path: &Path) -> Result<Vec<ManifestRow>> {
+    if path.extension().and_then(|e| e.to_str()) == Some("csv") {
+        let mut rows = Vec::new();
+        for row in csv::Reader::from_path(path)?.deserialize() {
+            rows.push(row?);
+        }
+        Ok(rows)
+    } else {
+        let file = File::open(path).with_context(|| format!("opening manifest {path:?}"))?;
+        Ok(serde_json::from_reader(file)?)
+    }
+}
+
+#[cfg(test)]
+mod manifest_row_tests {
+    use super::*;
+
+    /// A `derived_from`/`attributes` cell followed by more columns used to
+    /// misalign every column after it, since `csv`'s serde support only
+    /// special-cases a trailing `Vec` field. Pins that a populated cell in
+    /// either position still lines up with the columns that follow it.
+    #[test]
+    fn csv_vec_cells_do_not_misalign_trailing_columns() {
+        let csv = "owner,file,ipfs_location,asset_canister,uri,sha2,sha2_auto,upload_ipfs,mime_type,attributes,metadata_json,verify,derived_from,derive_method,derive_params\n\
+                    2vxsx-fae,,,,,,false,false,,a;b;c,,false,1;2;3,manual,params\n";
+        let mut rows: Vec<ManifestRow> = Vec::new();
+        for row in csv::Reader::from_reader(csv.as_bytes()).deserialize() {
+            rows.push(row.unwrap());
+        }
+        let row = rows.into_iter().next().unwrap();
+        assert_eq!(row.attributes, vec!["a", "b", "c"]);
+        assert_eq!(row.derived_from, vec![1, 2, 3]);
+        assert_eq!(row.derive_method.as_deref(), Some("manual"));
+        assert_eq!(row.derive_params.as_deref(), Some("params"));
+    }
+}
+
+/// The top-level single-token flags (`--wallet`, `--verify`, `--attribute`,
+/// `--metadata-json`, `--derived-from`, `--derive-method`, `--derive-params`,
+/// `--ipfs-gateway`, `--upload-ipfs` and its pinning-service settings),
+/// applied as a per-row fallback whenever a `--manifest` row leaves the
+/// corresponding field unset, so a manifest run isn't forced to repeat the
+/// same flags in every row.
+struct BatchDefaults {
+    wallet: Option<Principal>,
+    ipfs_gateway: String,
+    verify: bool,
+    attributes: Vec<String>,
+    metadata_json: Option<PathBuf>,
+    derived_from: Vec<u64>,
+    derive_method: Option<String>,
+    derive_params: Option<String>,
+    upload_ipfs: bool,
+    ipfs_pin_endpoint: String,
+    ipfs_pin_token: Option<String>,
+}
+
+/// Mints every row of a manifest in turn, printing a progress summary and
+/// each resulting [`MintReceipt`]. On a [`MintError`] for a row, prompts to
+/// continue with the remaining rows or abort, unless `--yes` was passed.
+async fn mint_batch(
+    agent: &Agent,
+    canister: Principal,
+    manifest: &Path,
+    defaults: BatchDefaults,
+    yes: bool,
+) -> Result<()> {
+    let rows = read_manifest(manifest)?;
+    let total = rows.len();
+    let mut minted = 0;
+    for (i, row) in rows.into_iter().enumerate() {
+        println!("[{}/{total}] minting to {}...", i + 1, row.owner);
+        let owner = row.owner;
+        let ipfs_location = if row.upload_ipfs || defaults.upload_ipfs {
+            let file = row
+                .file
+                .as_deref()
+                .with_context(|| format!("row {}: --upload-ipfs requires a file", i + 1))?;
+            let token = defaults
+                .ipfs_pin_token
+                .as_deref()
+                .context("--ipfs-pin-token is required with --upload-ipfs")?;
+            let cid = ipfs::upload(file, &defaults.ipfs_pin_endpoint, token).await?;
+            println!("  uploaded {} to IPFS as {cid}", file.display());
+            Some(cid.to_string())
+        } else {
+            row.ipfs_location
+        };
+        let source = Source {
+            ipfs_location,
+            asset_canister: row.asset_canister,
+            uri: row.uri,
+            file: row.file,
+            sha2: row.sha2,
+            sha2_auto: row.sha2_auto,
+            mime_type: row.mime_type,
+            attributes: if row.attributes.is_empty() {
+                defaults.attributes.clone()
+            } else {
+                row.attributes
+            },
+            metadata_json: row.metadata_json.or_else(|| defaults.metadata_json.clone()),
+            verify: row.verify || defaults.verify,
+            ipfs_gateway: defaults.ipfs_gateway.clone(),
+            derived_from: if row.derived_from.is_empty() {
+                defaults.derived_from.clone()
+            } else {
+                row.derived_from
+            },
+            derive_method: row.derive_method.or_else(|| defaults.derive_method.clone()),
+            derive_params: row.derive_params.or_else(|| defaults.derive_params.clone()),
+        };
+        match mint_one(agent, canister, owner, source, defaults.wallet).await {
+            Ok(receipt) => {
+                minted += 1;
+                println!(
+                    "  minted token {} to {owner} (transaction id {})",
+                    receipt.token_id, receipt.id
+                );
+            }
+            Err(e) => {
+                eprintln!("  failed to mint to {owner}: {e}");
+                if !yes
+                    && !Confirm::new()
+                        .with_prompt("Continue minting the rest of the manifest?")
+                        .interact()?
+                {
+                    bail!("aborted after {minted}/{total} minted");
+                }
+            }
+        }
+    }
+    println!("Minted {minted}/{total} tokens from {manifest:?}");
     Ok(())
 }
 
@@ -134,23 +526,66 @@ async fn rmain() -> Result<()> {
 /// metadata. A SHA256 hash can also be supplied, and is required if the source
 /// is a URI, but can be calculated for you via the `--sha2-auto` flag.
 ///
+/// Alternatively, pass `--manifest` with a JSON or CSV file describing many
+/// tokens at once to mint a whole collection in one invocation.
+///
+/// Instead of providing a pre-computed `--ipfs-location`, pass `--upload-ipfs`
+/// to have the tool pack `--file` into a CAR and pin it itself, guaranteeing
+/// the CID minted on-chain matches the bytes that get pinned.
+///
+/// Use `--attribute key=value` (repeatable) or `--metadata-json` to attach
+/// OpenSea-style traits so the token is marketplace-readable.
+///
+/// By default the `dfx` default identity is used; pass `--identity <name>`
+/// to mint as a different one. Secp256k1 and Ed25519 PEMs are both
+/// supported, and an encrypted PEM prompts for its passphrase.
+///
+/// Pass `--verify` to fetch a remote source client-side and confirm its
+/// SHA-256 matches the advertised `contentHash` before minting anything.
+///
+/// For derivative tokens, pass `--derived-from <token_id>` (repeatable)
+/// along with `--derive-method` and `--derive-params` to record provenance:
+/// the generation method, its parameters, and the source tokens.
+///
 /// DFINITY's dip721-nft-container canister supports the minting operation, but
 /// not all canisters do. Additionally, each canister differs in who is
 /// authorized to mint; usually only the original canister creator is. That may
 /// mean your wallet, rather than your DFX principal, depending on how the
-/// canister was initialized. Either of these things can cause an error.
+/// canister was initialized. Either of these things can cause an error. If
+/// it's your wallet, pass `--wallet <principal>` to mint through it.
 #[derive(Parser)]
 struct Args {
     /// The network the canister is running on. Can be 'ic', 'local', or a URL.
     network: String,
     /// The DIP-721 compliant NFT container.
     canister: Principal,
-    /// The owner of the new NFT.
-    #[clap(long)]
-    owner: Principal,
+    /// The owner of the new NFT. Required unless `--manifest` is specified.
+    #[clap(long, required_unless_present("manifest"))]
+    owner: Option<Principal>,
+    /// Mints an entire collection from a manifest file (JSON array or CSV) of
+    /// rows, each describing one token's owner and content source. Conflicts
+    /// with the single-token flags below.
+    #[clap(
+        long,
+        conflicts_with_all(&["owner", "ipfs-location", "asset-canister", "uri", "file", "sha2", "sha2-auto", "mime-type"])
+    )]
+    manifest: Option<PathBuf>,
     /// The CID of the file on IPFS.
-    #[clap(long, conflicts_with_all(&["asset-canister", "uri"]))]
+    #[clap(long, conflicts_with_all(&["asset-canister", "uri", "upload-ipfs"]))]
     ipfs_location: Option<String>,
+    /// Uploads `--file` to an IPFS pinning service and fills in
+    /// `--ipfs-location` with the resulting CID automatically, so the CID
+    /// minted on-chain is guaranteed to match the bytes actually pinned. With
+    /// `--manifest`, applies to every row that doesn't set its own `file`/
+    /// `ipfs_location`.
+    #[clap(long, conflicts_with_all(&["ipfs-location", "asset-canister", "uri"]))]
+    upload_ipfs: bool,
+    /// The IPFS pinning service endpoint the packed CAR is POSTed to.
+    #[clap(long, requires("upload-ipfs"), default_value = "https://api.web3.storage/car")]
+    ipfs_pin_endpoint: String,
+    /// The bearer token for the IPFS pinning service. Required with `--upload-ipfs`.
+    #[clap(long, requires("upload-ipfs"))]
+    ipfs_pin_token: Option<String>,
     /// The principal of the file's asset canister on the IC.
     #[clap(long, conflicts_with_all(&["ipfs-location", "uri"]))]
     asset_canister: Option<Principal>,
@@ -159,7 +594,7 @@ struct Args {
     uri: Option<String>,
     /// The path to the file. Required if you want the file contents sent to
     /// the smart contract.
-    #[clap(long, required_unless_present_any(&["asset-canister", "uri", "ipfs-location"]))]
+    #[clap(long, required_unless_present_any(&["asset-canister", "uri", "ipfs-location", "manifest"]))]
     file: Option<PathBuf>,
     /// The SHA-256 hash of the file. SHA2 is required if `--uri` is specified
     #[clap(long, group("hash"))]
@@ -169,38 +604,65 @@ struct Args {
     sha2_auto: bool,
     /// The MIME type of the file. Can be inferred if `--file` is specified,
     /// required otherwise.
-    #[clap(long, required_unless_present("file"))]
+    #[clap(long, required_unless_present_any(&["file", "manifest"]))]
     mime_type: Option<String>,
-    /// Skips confirmation for a minted NFT with no `--file`.
+    /// Skips confirmation for a minted NFT with no `--file`, and for
+    /// continuing past a failed row when `--manifest` is specified.
     #[clap(short)]
     yes: bool,
     /// Fetches the root key for the network. Auto-set for `--network local`. Do not use this with real data or on the real IC.
     #[clap(long)]
     fetch_root_key: bool,
+    /// Mints through this cycles wallet canister instead of calling
+    /// `canister` directly, for collections whose authorized custodian is
+    /// your wallet rather than your DFX principal.
+    #[clap(long)]
+    wallet: Option<Principal>,
+    /// A trait to attach to the token, in `key=value` form (repeatable).
+    /// Numeric values are stored as `NatContent`, everything else as
+    /// `TextContent`.
+    #[clap(long = "attribute")]
+    attributes: Vec<String>,
+    /// A JSON file with an OpenSea-style `{name, description, attributes:
+    /// [{trait_type, value, display_type}]}` document to attach to the token.
+    #[clap(long)]
+    metadata_json: Option<PathBuf>,
+    /// The name of the `dfx` identity to mint with. Defaults to the
+    /// configured default identity. Works with Secp256k1 and Ed25519 keys,
+    /// and prompts for a passphrase if the PEM is encrypted.
+    #[clap(long)]
+    identity: Option<String>,
+    /// Fetches the referenced remote content (`--uri`, `--ipfs-location`, or
+    /// `--asset-canister`) and aborts the mint unless its SHA-256 matches
+    /// the advertised `contentHash`, so a stale or mistyped hash can't be
+    /// minted on-chain.
+    #[clap(long)]
+    verify: bool,
+    /// The IPFS gateway used to resolve `--ipfs-location` when `--verify` is set.
+    #[clap(long, default_value = "https://ipfs.io")]
+    ipfs_gateway: String,
+    /// The token id of a source NFT this token was derived from (repeatable,
+    /// in order). Recorded as provenance metadata alongside `--derive-method`
+    /// and `--derive-params`.
+    #[clap(long)]
+    derived_from: Vec<u64>,
+    /// The name of the generation method used to derive this token from
+    /// `--derived-from`'s source tokens.
+    #[clap(long, requires("derived-from"))]
+    derive_method: Option<String>,
+    /// The parameters passed to `--derive-method` when generating this token.
+    #[clap(long, requires("derive-method"))]
+    derive_params: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct DefaultIdentity {
-    default: String,
-}
-
-async fn get_agent(network: &str, fetch_root_key: bool) -> Result<Agent> {
+async fn get_agent(network: &str, identity_name: Option<&str>, fetch_root_key: bool) -> Result<Agent> {
     let url = match network {
         "local" => "http://localhost:4943",
         "ic" => "https://ic0.app",
         url => url,
     };
-    let user_home = env::var_os("HOME").unwrap();
-    let file = File::open(Path::new(&user_home).join(".config/dfx/identity.json"))
-        .context("Configure an identity in `dfx` or provide an --identity flag")?;
-    let default: DefaultIdentity = serde_json::from_reader(file)?;
-    let pemfile = PathBuf::from_iter([
-        &*user_home,
-        ".config/dfx/identity/".as_ref(),
-        default.default.as_ref(),
-        "identity.pem".as_ref(),
-    ]);
-    let identity = BasicIdentity::from_pem_file(pemfile)?;
+    let user_home = PathBuf::from(env::var_os("HOME").unwrap());
+    let identity = identity::load(&user_home, identity_name)?;
     let agent = Agent::builder()
         .with_transport(ReqwestHttpReplicaV2Transport::create(url)?)
         .with_identity(identity)
@@ -0,0 +1,40 @@
+//! Derivative-NFT provenance metadata for `--derived-from`, `--derive-method`,
+//! and `--derive-params`: records the generation method, its parameters, and
+//! the ordered source token ids a derived token was produced from, the way
+//! the dNFT contract stores method/params/source_ids in its metadata
+//! extension.
+
+use std::collections::HashMap;
+
+use crate::MetadataVal;
+
+/// Inserts a provenance record into `key_val_data` if any derivation flags
+/// were given. The source id list is packed as little-endian `u64`s so
+/// downstream viewers can reconstruct the derivation graph without a JSON
+/// parser.
+pub fn apply(
+    key_val_data: &mut HashMap<String, MetadataVal>,
+    derived_from: &[u64],
+    method: Option<&str>,
+    params: Option<&str>,
+) {
+    if let Some(method) = method {
+        key_val_data.insert(
+            "deriveMethod".to_string(),
+            MetadataVal::TextContent(method.to_string()),
+        );
+    }
+    if let Some(params) = params {
+        key_val_data.insert(
+            "deriveParams".to_string(),
+            MetadataVal::TextContent(params.to_string()),
+        );
+    }
+    if !derived_from.is_empty() {
+        let mut packed = Vec::with_capacity(derived_from.len() * 8);
+        for id in derived_from {
+            packed.extend_from_slice(&id.to_le_bytes());
+        }
+        key_val_data.insert("derivedFrom".to_string(), MetadataVal::BlobContent(packed));
+    }
+}
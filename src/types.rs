@@ -2,10 +2,12 @@
 
 use std::collections::HashMap;
 
+use candid::Principal;
+
 #[derive(CandidType)]
 pub struct MetadataPart<'a> {
     pub purpose: MetadataPurpose,
-    pub key_val_data: HashMap<&'static str, MetadataVal>,
+    pub key_val_data: HashMap<String, MetadataVal>,
     pub data: &'a [u8],
 }
 
@@ -50,3 +52,41 @@ pub struct MintReceipt {
     pub id: u128,
     pub token_id: u64,
 }
+
+/// Arguments to a cycles wallet's `wallet_call128`, which forwards a call to
+/// `canister` on the wallet's behalf, making the wallet the effective caller.
+#[derive(CandidType)]
+pub struct WalletCallArgs<'a> {
+    pub canister: Principal,
+    pub method_name: &'a str,
+    pub args: &'a [u8],
+    pub cycles: u128,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct WalletCallResult {
+    #[serde(rename = "return")]
+    pub return_: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum WalletCallResponse {
+    Ok(WalletCallResult),
+    Err(String),
+}
+
+/// The standard IC `http_request` query, as served by the asset canister.
+#[derive(CandidType)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
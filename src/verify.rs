@@ -0,0 +1,87 @@
+//! Client-side content-hash verification for `--verify`: resolves a token's
+//! remote source the way a viewer would and checks its SHA-256 against the
+//! advertised `contentHash` before anything is minted.
+
+use anyhow::{bail, Context, Result};
+use candid::Principal;
+use ic_agent::Agent;
+use sha2::{Digest, Sha256};
+
+use crate::types::{HttpRequest, HttpResponse};
+
+/// Fetches whichever remote source is set and aborts unless its SHA-256
+/// matches `expected` (the `contentHash` that would otherwise be minted
+/// unchecked).
+pub async fn check(
+    agent: &Agent,
+    gateway: &str,
+    ipfs_location: Option<&str>,
+    asset_canister: Option<Principal>,
+    uri: Option<&str>,
+    expected: &[u8],
+) -> Result<()> {
+    let Some(bytes) = fetch_remote(agent, gateway, ipfs_location, asset_canister, uri).await?
+    else {
+        return Ok(());
+    };
+    let actual = Sha256::digest(&bytes);
+    if actual.as_slice() != expected {
+        bail!(
+            "content hash mismatch: advertised {} but the fetched content hashes to {}",
+            hex::encode(expected),
+            hex::encode(actual)
+        );
+    }
+    Ok(())
+}
+
+async fn fetch_remote(
+    agent: &Agent,
+    gateway: &str,
+    ipfs_location: Option<&str>,
+    asset_canister: Option<Principal>,
+    uri: Option<&str>,
+) -> Result<Option<Vec<u8>>> {
+    if let Some(cid) = ipfs_location {
+        let url = format!("{}/ipfs/{cid}", gateway.trim_end_matches('/'));
+        Ok(Some(http_get(&url).await?))
+    } else if let Some(canister) = asset_canister {
+        Ok(Some(fetch_asset(agent, canister).await?))
+    } else if let Some(uri) = uri {
+        Ok(Some(http_get(uri).await?))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn http_get(url: &str) -> Result<Vec<u8>> {
+    let res = reqwest::get(url)
+        .await
+        .with_context(|| format!("fetching {url}"))?
+        .error_for_status()
+        .with_context(|| format!("fetching {url}"))?;
+    Ok(res.bytes().await?.to_vec())
+}
+
+async fn fetch_asset(agent: &Agent, canister: Principal) -> Result<Vec<u8>> {
+    let request = HttpRequest {
+        method: "GET".to_string(),
+        url: "/".to_string(),
+        headers: vec![],
+        body: vec![],
+    };
+    let res = agent
+        .query(&canister, "http_request")
+        .with_arg(Encode!(&request)?)
+        .call()
+        .await
+        .with_context(|| format!("querying http_request on asset canister {canister}"))?;
+    let response = Decode!(&res, HttpResponse)?;
+    if response.status_code >= 300 {
+        bail!(
+            "asset canister {canister} returned HTTP {}",
+            response.status_code
+        );
+    }
+    Ok(response.body)
+}